@@ -0,0 +1,40 @@
+//! Error types surfaced while reading or writing block export files.
+
+/// Errors that can occur when reading or writing blocks from/to a file with
+/// [`BlockFileCodec`](crate::file_codec::BlockFileCodec).
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum FileClientError {
+    /// An error occurred while decoding a block body from its RLP bytes.
+    #[error("{0}")]
+    Rlp(alloy_rlp::Error, Vec<u8>),
+
+    /// An error occurred reading or writing the underlying file.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// Failed to decompress or compress a `.rlp.zst`/`.rlp.sz` block file.
+    #[error("failed to (de)compress block file: {0}")]
+    Decompression(std::io::Error),
+
+    /// A decoded block's CRC32 didn't match the checksum stored alongside it in its frame.
+    #[error("checksum mismatch at offset {offset}: expected {expected:08x}, got {got:08x}")]
+    ChecksumMismatch {
+        /// The checksum recorded in the frame header.
+        expected: u32,
+        /// The checksum actually computed over the frame's payload.
+        got: u32,
+        /// Byte offset of the start of the frame within the file.
+        offset: u64,
+    },
+
+    /// A frame's declared payload length exceeded [`MAX_BLOCK_FRAME_PAYLOAD_LEN`](crate::file_codec::MAX_BLOCK_FRAME_PAYLOAD_LEN).
+    #[error("frame at offset {offset} declares a {len} byte payload, exceeding the {max} byte limit")]
+    FrameTooLarge {
+        /// The payload length read from the frame header.
+        len: usize,
+        /// The maximum payload length a frame is allowed to declare.
+        max: usize,
+        /// Byte offset of the start of the frame within the file.
+        offset: u64,
+    },
+}
@@ -1,28 +1,112 @@
 //! Codec for reading raw block bodies from a file.
 
 use crate::file_client::FileClientError;
-use alloy_primitives::bytes::{Buf, BytesMut};
+use alloy_primitives::bytes::{Buf, BufMut, BytesMut};
 use alloy_rlp::{Decodable, Encodable};
 use tokio_util::codec::{Decoder, Encoder};
 
+/// Size in bytes of the `[len: u32][crc32: u32]` frame header each encoded block is prefixed
+/// with, see [`BlockFileCodec`].
+const FRAME_HEADER_LEN: usize = 8;
+
+/// Largest payload length a frame is allowed to declare. The length prefix isn't covered by the
+/// frame's CRC32, so this bounds how much a single corrupted length field can make the decoder
+/// reserve ahead of the checksum actually being verified. Chosen generously above the largest
+/// real block body, far above even a block whose whole gas limit is spent on calldata.
+pub(crate) const MAX_BLOCK_FRAME_PAYLOAD_LEN: usize = 128 * 1024 * 1024;
+
+/// Caps how much of the buffered input is decoded per batch, i.e. across the [`Decoder::decode`]
+/// polls between two calls to `reset_batch`, letting an importer interleave decoding with downstream stage processing and keep peak memory bounded,
+/// analogous to the batching knobs streaming zstd decoders expose.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BlockDecodingStrategy {
+    /// Decode everything currently buffered.
+    #[default]
+    All,
+    /// Decode at most this many blocks per batch, returning `Ok(None)` once the limit is hit
+    /// even if more blocks are buffered.
+    Blocks(usize),
+    /// Stop decoding once this many bytes of frames have been decoded in the batch, returning
+    /// `Ok(None)` even if more bytes are buffered. The limit is checked before each frame, so the
+    /// frame that crosses it is still decoded whole and a batch can exceed it by up to one frame.
+    Bytes(usize),
+}
+
 /// Codec for reading raw block bodies from a file.
 ///
-/// If using with [`FramedRead`](tokio_util::codec::FramedRead), the user should make sure the
-/// framed reader has capacity for the entire block file. Otherwise, the decoder will return
-/// [`InputTooShort`](alloy_rlp::Error::InputTooShort), because RLP headers can only be
-/// decoded if the internal buffer is large enough to contain the entire block body.
-///
-/// Without ensuring the framed reader has capacity for the entire file, a block body is likely to
-/// fall across two read buffers, the decoder will not be able to decode the header, which will
-/// cause it to fail.
+/// By default this reads and writes plain, concatenated RLP, the format real `.rlp` block
+/// exports use: a block body is always encoded as an RLP list, so [`decode`](Decoder::decode)
+/// peeks at the list header to work out how many bytes the full frame needs before attempting to
+/// decode it, composing with [`FramedRead`](tokio_util::codec::FramedRead) the same way any
+/// other length-delimited codec does, even when a block body straddles two reads.
 ///
-/// It's recommended to use [`with_capacity`](tokio_util::codec::FramedRead::with_capacity) to set
-/// the capacity of the framed reader to the size of the file.
-pub(crate) struct BlockFileCodec<B>(std::marker::PhantomData<B>);
+/// Calling [`Self::with_checksum_framing`] opts into wrapping each block in a self-describing
+/// frame instead, inspired by the Snappy frame format: a little-endian `u32` payload length, a
+/// little-endian `u32` CRC32 (crc32fast) over the RLP-encoded block, then the RLP bytes
+/// themselves. This lets corruption be detected rather than silently misparsed as RLP, at the
+/// cost of the file no longer being plain RLP that other tools can produce or read.
+pub(crate) struct BlockFileCodec<B> {
+    /// Byte offset into the file of the next frame to be decoded, used to annotate
+    /// [`FileClientError::ChecksumMismatch`].
+    offset: u64,
+    /// How much of the buffered input a single `decode` poll is allowed to consume.
+    strategy: BlockDecodingStrategy,
+    /// Number of blocks decoded in the current batch, reset by [`Self::reset_batch`].
+    blocks_in_batch: usize,
+    /// Number of frame bytes decoded in the current batch, reset by [`Self::reset_batch`].
+    bytes_in_batch: usize,
+    /// Whether blocks are wrapped in the `[len: u32][crc32: u32]` checksum frame, set by
+    /// [`Self::with_checksum_framing`]. Plain concatenated RLP otherwise.
+    checksum_framing: bool,
+    _marker: std::marker::PhantomData<B>,
+}
 
 impl<B> Default for BlockFileCodec<B> {
     fn default() -> Self {
-        Self(std::marker::PhantomData)
+        Self {
+            offset: 0,
+            strategy: BlockDecodingStrategy::All,
+            blocks_in_batch: 0,
+            bytes_in_batch: 0,
+            checksum_framing: false,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<B> BlockFileCodec<B> {
+    /// Creates a codec that caps each batch of decoded blocks according to `strategy`.
+    pub(crate) fn with_strategy(strategy: BlockDecodingStrategy) -> Self {
+        Self { strategy, ..Self::default() }
+    }
+
+    /// Opts this codec into the `[len: u32][crc32: u32]` checksum frame wrapper for both reading
+    /// and writing, instead of plain concatenated RLP, see the type docs. Composes with the
+    /// other constructors, e.g. `BlockFileCodec::with_strategy(strategy).with_checksum_framing()`.
+    pub(crate) fn with_checksum_framing(mut self) -> Self {
+        self.checksum_framing = true;
+        self
+    }
+
+    /// Clears the current batch's counters, allowing a new batch to be decoded from this point.
+    pub(crate) fn reset_batch(&mut self) {
+        self.blocks_in_batch = 0;
+        self.bytes_in_batch = 0;
+    }
+
+    /// Clears all partial-frame and batch state, so the same codec can be reused across files.
+    pub(crate) fn reset(&mut self) {
+        self.offset = 0;
+        self.reset_batch();
+    }
+
+    /// Returns `true` if the current batch has already hit the configured [`BlockDecodingStrategy`] limit.
+    fn batch_is_full(&self) -> bool {
+        match self.strategy {
+            BlockDecodingStrategy::All => false,
+            BlockDecodingStrategy::Blocks(limit) => self.blocks_in_batch >= limit,
+            BlockDecodingStrategy::Bytes(limit) => self.bytes_in_batch >= limit,
+        }
     }
 }
 
@@ -31,13 +115,110 @@ impl<B: Decodable> Decoder for BlockFileCodec<B> {
     type Error = FileClientError;
 
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.batch_is_full() {
+            return Ok(None)
+        }
+
+        if self.checksum_framing {
+            self.decode_checksum_framed(src)
+        } else {
+            self.decode_plain_rlp(src)
+        }
+    }
+}
+
+impl<B: Decodable> BlockFileCodec<B> {
+    /// Decodes plain, unframed, concatenated RLP, see the type docs.
+    fn decode_plain_rlp(&mut self, src: &mut BytesMut) -> Result<Option<B>, FileClientError> {
         if src.is_empty() {
             return Ok(None)
         }
 
+        // A block body is always an RLP list, so the first byte tells us how the list header
+        // is shaped: a short list encodes its payload length directly in the first byte, a long
+        // list spills the payload length into the following bytes.
+        let first_byte = src[0];
+        let (header_len, payload_len) = match first_byte {
+            0xc0..=0xf7 => (1, (first_byte - 0xc0) as usize),
+            0xf8..=0xff => {
+                let len_of_len = (first_byte - 0xf7) as usize;
+                if src.len() < 1 + len_of_len {
+                    src.reserve(1 + len_of_len - src.len());
+                    return Ok(None)
+                }
+                let payload_len = src[1..1 + len_of_len]
+                    .iter()
+                    .fold(0usize, |len, &byte| (len << 8) | byte as usize);
+                (1 + len_of_len, payload_len)
+            }
+            // Not a list header: let the RLP decoder below produce the appropriate error.
+            _ => (0, 0),
+        };
+
+        let frame_len = header_len + payload_len;
+        if frame_len > 0 && src.len() < frame_len {
+            src.reserve(frame_len - src.len());
+            return Ok(None)
+        }
+
         let buf_slice = &mut src.as_ref();
         let body = B::decode(buf_slice).map_err(|err| FileClientError::Rlp(err, src.to_vec()))?;
-        src.advance(src.len() - buf_slice.len());
+        let consumed = src.len() - buf_slice.len();
+        src.advance(consumed);
+
+        self.offset += consumed as u64;
+        self.blocks_in_batch += 1;
+        self.bytes_in_batch += consumed;
+
+        Ok(Some(body))
+    }
+
+    /// Decodes a block wrapped in the `[len: u32][crc32: u32]` checksum frame, see the type docs.
+    fn decode_checksum_framed(&mut self, src: &mut BytesMut) -> Result<Option<B>, FileClientError> {
+        if src.len() < FRAME_HEADER_LEN {
+            if !src.is_empty() {
+                src.reserve(FRAME_HEADER_LEN - src.len());
+            }
+            return Ok(None)
+        }
+
+        let payload_len = u32::from_le_bytes(src[0..4].try_into().unwrap()) as usize;
+        let expected_crc = u32::from_le_bytes(src[4..8].try_into().unwrap());
+
+        // The length prefix itself isn't covered by the CRC below, so a single corrupted byte
+        // here must not be able to force an unbounded `reserve` ahead of the checksum check.
+        if payload_len > MAX_BLOCK_FRAME_PAYLOAD_LEN {
+            return Err(FileClientError::FrameTooLarge {
+                len: payload_len,
+                max: MAX_BLOCK_FRAME_PAYLOAD_LEN,
+                offset: self.offset,
+            })
+        }
+
+        let frame_len = FRAME_HEADER_LEN + payload_len;
+        if src.len() < frame_len {
+            src.reserve(frame_len - src.len());
+            return Ok(None)
+        }
+
+        let payload = &src[FRAME_HEADER_LEN..frame_len];
+        let got_crc = crc32fast::hash(payload);
+        if got_crc != expected_crc {
+            return Err(FileClientError::ChecksumMismatch {
+                expected: expected_crc,
+                got: got_crc,
+                offset: self.offset,
+            })
+        }
+
+        let mut buf_slice = payload;
+        let body =
+            B::decode(&mut buf_slice).map_err(|err| FileClientError::Rlp(err, payload.to_vec()))?;
+
+        self.offset += frame_len as u64;
+        self.blocks_in_batch += 1;
+        self.bytes_in_batch += frame_len;
+        src.advance(frame_len);
 
         Ok(Some(body))
     }
@@ -47,7 +228,427 @@ impl<B: Encodable> Encoder<B> for BlockFileCodec<B> {
     type Error = FileClientError;
 
     fn encode(&mut self, item: B, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        item.encode(dst);
+        if !self.checksum_framing {
+            item.encode(dst);
+            return Ok(())
+        }
+
+        let mut payload = BytesMut::new();
+        item.encode(&mut payload);
+
+        dst.put_u32_le(payload.len() as u32);
+        dst.put_u32_le(crc32fast::hash(&payload));
+        dst.extend_from_slice(&payload);
+
         Ok(())
     }
 }
+
+/// Compression framing used by an archived block export, detected from its leading magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BlockFileCompression {
+    /// Raw, uncompressed, concatenated RLP.
+    None,
+    /// Zstandard-compressed, framed with the standard zstd magic number.
+    Zstd,
+    /// Snappy-compressed, framed per the [Snappy frame format].
+    ///
+    /// [Snappy frame format]: https://github.com/google/snappy/blob/main/framing_format.txt
+    Snappy,
+}
+
+impl BlockFileCompression {
+    /// Magic number identifying a zstd frame, see [RFC 8878].
+    ///
+    /// [RFC 8878]: https://datatracker.ietf.org/doc/html/rfc8878#section-3.1.1
+    const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+    /// Chunk header of the stream identifier chunk that opens every Snappy-framed stream, used
+    /// to detect the format on read.
+    const SNAPPY_MAGIC: [u8; 4] = [0xff, 0x06, 0x00, 0x00];
+
+    /// Full stream identifier chunk (header plus the `sNaPpY` literal) written once at the
+    /// start of every Snappy-framed stream on write.
+    const SNAPPY_STREAM_IDENTIFIER: [u8; 10] =
+        [0xff, 0x06, 0x00, 0x00, b's', b'N', b'a', b'P', b'p', b'Y'];
+
+    /// Detects the compression format from the leading bytes of a block file.
+    ///
+    /// Returns `None` if `src` doesn't yet hold enough bytes to decide.
+    fn detect(src: &[u8]) -> Option<Self> {
+        if src.len() < 4 {
+            return None
+        }
+
+        Some(if src[..4] == Self::ZSTD_MAGIC {
+            Self::Zstd
+        } else if src[..4] == Self::SNAPPY_MAGIC {
+            Self::Snappy
+        } else {
+            Self::None
+        })
+    }
+
+    /// Computes the masked CRC32C checksum the Snappy frame format stores alongside each chunk,
+    /// see the [format spec](https://github.com/google/snappy/blob/main/framing_format.txt#L64-L73).
+    fn mask_crc32c(data: &[u8]) -> u32 {
+        let crc = crc32c::crc32c(data);
+        (crc.rotate_right(15)).wrapping_add(0xa282ead8)
+    }
+}
+
+/// Wraps [`BlockFileCodec`] so it can stream through a zstd- or Snappy-compressed block file
+/// transparently, so [`FileClient`](crate::file_client::FileClient) can ingest `.rlp.zst` and
+/// `.rlp.sz` exports the same way it ingests raw `.rlp` ones.
+///
+/// Compression is detected once from the file's magic bytes and then decoded one frame at a
+/// time into an internal buffer, handing the decompressed bytes off to the inner
+/// [`BlockFileCodec`] as they become available, rather than inflating the whole file into memory
+/// up front. The inner codec defaults to plain concatenated RLP, so a genuine `.rlp.zst`/`.rlp.sz`
+/// archive (zstd/Snappy over plain RLP) decodes out of the box; call
+/// [`Self::with_checksum_framing`] if the archive was itself written with the optional checksum
+/// frame wrapper.
+pub(crate) struct CompressedBlockFileCodec<B> {
+    /// Compression format of the file, set by [`Self::with_compression`] or otherwise detected
+    /// lazily from its leading magic bytes.
+    compression: Option<BlockFileCompression>,
+    /// Decompressed RLP bytes that haven't been consumed by `inner` yet.
+    decompressed: BytesMut,
+    /// The underlying raw RLP codec, run over `decompressed` rather than `src` directly.
+    inner: BlockFileCodec<B>,
+    /// Streaming zstd decoder, created once the format is known to be [`BlockFileCompression::Zstd`].
+    zstd_decoder: Option<zstd::stream::raw::Decoder<'static>>,
+    /// Whether the Snappy stream-identifier chunk has already been written to the output.
+    snappy_header_written: bool,
+}
+
+impl<B> Default for CompressedBlockFileCodec<B> {
+    fn default() -> Self {
+        Self {
+            compression: None,
+            decompressed: BytesMut::new(),
+            inner: BlockFileCodec::default(),
+            zstd_decoder: None,
+            snappy_header_written: false,
+        }
+    }
+}
+
+impl<B> CompressedBlockFileCodec<B> {
+    /// Creates a codec that caps each batch of decoded blocks according to `strategy`, see
+    /// [`BlockFileCodec::with_strategy`].
+    pub(crate) fn with_strategy(strategy: BlockDecodingStrategy) -> Self {
+        Self { inner: BlockFileCodec::with_strategy(strategy), ..Self::default() }
+    }
+
+    /// Creates a codec that writes blocks compressed with `compression`, e.g. to produce a new
+    /// `.rlp.zst`/`.rlp.sz` export. The read path then also assumes `compression` instead of
+    /// detecting it from the file's magic bytes, until [`Self::reset`] clears the setting.
+    pub(crate) fn with_compression(compression: BlockFileCompression) -> Self {
+        Self { compression: Some(compression), ..Self::default() }
+    }
+
+    /// Opts the inner [`BlockFileCodec`] into the checksum frame wrapper, see
+    /// [`BlockFileCodec::with_checksum_framing`]. Composes with the other constructors, e.g.
+    /// `CompressedBlockFileCodec::with_compression(Zstd).with_checksum_framing()`.
+    pub(crate) fn with_checksum_framing(mut self) -> Self {
+        self.inner = self.inner.with_checksum_framing();
+        self
+    }
+
+    /// Clears the current batch's counters, allowing a new batch to be decoded from this point.
+    pub(crate) fn reset_batch(&mut self) {
+        self.inner.reset_batch();
+    }
+
+    /// Clears all decompression, partial-frame and batch state, so the same codec can be reused
+    /// across files.
+    pub(crate) fn reset(&mut self) {
+        self.compression = None;
+        self.decompressed.clear();
+        self.zstd_decoder = None;
+        self.snappy_header_written = false;
+        self.inner.reset();
+    }
+
+    /// Feeds as much of `src` as can currently be decompressed into `self.decompressed`,
+    /// advancing `src` past whatever compressed bytes were consumed.
+    fn decompress(&mut self, src: &mut BytesMut) -> Result<(), FileClientError> {
+        let compression = match self.compression {
+            Some(compression) => compression,
+            None => match BlockFileCompression::detect(src) {
+                Some(compression) => {
+                    self.compression = Some(compression);
+                    compression
+                }
+                None => return Ok(()),
+            },
+        };
+
+        match compression {
+            BlockFileCompression::None => {
+                self.decompressed.unsplit(src.split());
+                Ok(())
+            }
+            BlockFileCompression::Zstd => {
+                let decoder = self
+                    .zstd_decoder
+                    .get_or_insert_with(|| zstd::stream::raw::Decoder::new().expect("zstd decoder"));
+
+                let mut in_buffer = zstd::stream::raw::InBuffer::around(src);
+                let mut out = [0u8; 64 * 1024];
+                loop {
+                    let mut out_buffer = zstd::stream::raw::OutBuffer::around(&mut out[..]);
+                    zstd::stream::raw::Operation::run(decoder, &mut in_buffer, &mut out_buffer)
+                        .map_err(FileClientError::Decompression)?;
+                    let written = out_buffer.pos();
+                    self.decompressed.extend_from_slice(&out[..written]);
+                    if written == 0 {
+                        break
+                    }
+                }
+                let consumed = in_buffer.pos();
+                src.advance(consumed);
+                Ok(())
+            }
+            BlockFileCompression::Snappy => {
+                // Each Snappy frame chunk is a 1-byte type tag followed by a 3-byte
+                // little-endian length, then that many bytes of chunk data.
+                while src.len() >= 4 {
+                    let chunk_type = src[0];
+                    let chunk_len =
+                        u32::from_le_bytes([src[1], src[2], src[3], 0]) as usize;
+                    if src.len() < 4 + chunk_len {
+                        break
+                    }
+
+                    let chunk = &src[4..4 + chunk_len];
+                    match chunk_type {
+                        // Stream identifier chunk, nothing to decompress.
+                        0xff => {}
+                        // Compressed data chunk: first 4 bytes are a CRC32C we don't re-verify
+                        // here, the rest is the Snappy-compressed block.
+                        0x00 => {
+                            let decompressed = snap::raw::Decoder::new()
+                                .decompress_vec(&chunk[4..])
+                                .map_err(|err| {
+                                    FileClientError::Decompression(std::io::Error::new(
+                                        std::io::ErrorKind::InvalidData,
+                                        err,
+                                    ))
+                                })?;
+                            self.decompressed.extend_from_slice(&decompressed);
+                        }
+                        // Uncompressed data chunk: same layout, no decompression needed.
+                        0x01 => self.decompressed.extend_from_slice(&chunk[4..]),
+                        // Padding or unknown skippable chunk; ignore its contents.
+                        _ => {}
+                    }
+
+                    src.advance(4 + chunk_len);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<B: Decodable> Decoder for CompressedBlockFileCodec<B> {
+    type Item = B;
+    type Error = FileClientError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        if self.inner.batch_is_full() {
+            return Ok(None)
+        }
+
+        self.decompress(src)?;
+        self.inner.decode(&mut self.decompressed)
+    }
+}
+
+impl<B: Encodable> Encoder<B> for CompressedBlockFileCodec<B> {
+    type Error = FileClientError;
+
+    fn encode(&mut self, item: B, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let mut framed = BytesMut::new();
+        self.inner.encode(item, &mut framed)?;
+
+        match self.compression {
+            Some(BlockFileCompression::Zstd) => {
+                let compressed = zstd::stream::encode_all(framed.as_ref(), 0)
+                    .map_err(FileClientError::Decompression)?;
+                dst.extend_from_slice(&compressed);
+                Ok(())
+            }
+            Some(BlockFileCompression::Snappy) => {
+                if !self.snappy_header_written {
+                    dst.extend_from_slice(&BlockFileCompression::SNAPPY_STREAM_IDENTIFIER);
+                    self.snappy_header_written = true;
+                }
+
+                // Snappy frame chunks carry at most 65536 bytes of uncompressed data each, so
+                // split the frame into chunk-sized pieces the way the format requires.
+                for uncompressed in framed.chunks(65536) {
+                    let compressed =
+                        snap::raw::Encoder::new().compress_vec(uncompressed).map_err(|err| {
+                            FileClientError::Decompression(std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                err,
+                            ))
+                        })?;
+
+                    let crc = BlockFileCompression::mask_crc32c(uncompressed);
+                    let chunk_len = 4 + compressed.len();
+
+                    // Compressed data chunk: type 0x00, 3-byte little-endian length, then a
+                    // masked CRC32C of the uncompressed data, then the compressed bytes.
+                    dst.put_u8(0x00);
+                    dst.extend_from_slice(&(chunk_len as u32).to_le_bytes()[..3]);
+                    dst.put_u32_le(crc);
+                    dst.extend_from_slice(&compressed);
+                }
+                Ok(())
+            }
+            Some(BlockFileCompression::None) | None => {
+                dst.extend_from_slice(&framed);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_plain(body: &[u8]) -> BytesMut {
+        let mut dst = BytesMut::new();
+        BlockFileCodec::<Vec<u8>>::default().encode(body.to_vec(), &mut dst).unwrap();
+        dst
+    }
+
+    fn encode_framed(body: &[u8]) -> BytesMut {
+        let mut dst = BytesMut::new();
+        BlockFileCodec::<Vec<u8>>::default()
+            .with_checksum_framing()
+            .encode(body.to_vec(), &mut dst)
+            .unwrap();
+        dst
+    }
+
+    #[test]
+    fn decodes_across_split_reads() {
+        // Plain, unframed RLP: the decoder must peek the list header itself to know how many
+        // more bytes a split read needs, rather than relying on a length prefix.
+        let frame = encode_plain(b"hello block");
+        let mut codec = BlockFileCodec::<Vec<u8>>::default();
+
+        let mut src = BytesMut::from(&frame[..frame.len() - 3]);
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+
+        src.extend_from_slice(&frame[frame.len() - 3..]);
+        let decoded = codec.decode(&mut src).unwrap().unwrap();
+        assert_eq!(decoded, b"hello block".to_vec());
+    }
+
+    #[test]
+    fn decodes_across_split_reads_with_long_list_header() {
+        // A body long enough to need the long-list RLP header form (first byte 0xf8..=0xff,
+        // spilling the payload length into following bytes) must also reserve correctly when
+        // split mid-header.
+        let body = vec![0u8; 100];
+        let frame = encode_plain(&body);
+        let mut codec = BlockFileCodec::<Vec<u8>>::default();
+
+        let mut src = BytesMut::from(&frame[..1]);
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+
+        src.extend_from_slice(&frame[1..]);
+        let decoded = codec.decode(&mut src).unwrap().unwrap();
+        assert_eq!(decoded, body);
+    }
+
+    #[test]
+    fn detects_checksum_mismatch() {
+        let mut frame = encode_framed(b"hello block");
+        let last = frame.len() - 1;
+        frame[last] ^= 0xff;
+
+        let mut codec = BlockFileCodec::<Vec<u8>>::default().with_checksum_framing();
+        let err = codec.decode(&mut frame).unwrap_err();
+        assert!(matches!(err, FileClientError::ChecksumMismatch { .. }));
+    }
+
+    #[test]
+    fn rejects_oversized_frame() {
+        let mut src = BytesMut::new();
+        src.put_u32_le((MAX_BLOCK_FRAME_PAYLOAD_LEN + 1) as u32);
+        src.put_u32_le(0);
+
+        let mut codec = BlockFileCodec::<Vec<u8>>::default().with_checksum_framing();
+        let err = codec.decode(&mut src).unwrap_err();
+        assert!(matches!(err, FileClientError::FrameTooLarge { .. }));
+    }
+
+    #[test]
+    fn batches_respect_block_limit() {
+        let mut src = BytesMut::new();
+        src.extend_from_slice(&encode_plain(b"one"));
+        src.extend_from_slice(&encode_plain(b"two"));
+        src.extend_from_slice(&encode_plain(b"three"));
+
+        let mut codec = BlockFileCodec::<Vec<u8>>::with_strategy(BlockDecodingStrategy::Blocks(2));
+        assert!(codec.decode(&mut src).unwrap().is_some());
+        assert!(codec.decode(&mut src).unwrap().is_some());
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+
+        codec.reset_batch();
+        assert!(codec.decode(&mut src).unwrap().is_some());
+    }
+
+    #[test]
+    fn compressed_codec_reads_real_zstd_archive() {
+        // Simulates a genuine `.rlp.zst` export produced by some other tool: plain, concatenated
+        // RLP compressed directly with zstd, never touching this codec's own (optional) checksum
+        // framing.
+        let mut plain = BytesMut::new();
+        plain.extend_from_slice(&encode_plain(b"first block"));
+        plain.extend_from_slice(&encode_plain(b"second block"));
+        let archive = zstd::stream::encode_all(plain.as_ref(), 0).unwrap();
+
+        let mut src = BytesMut::from(&archive[..]);
+        let mut reader = CompressedBlockFileCodec::<Vec<u8>>::default();
+        let first = reader.decode(&mut src).unwrap().unwrap();
+        assert_eq!(first, b"first block".to_vec());
+        let second = reader.decode(&mut src).unwrap().unwrap();
+        assert_eq!(second, b"second block".to_vec());
+    }
+
+    #[test]
+    fn compressed_codec_round_trips_zstd() {
+        let mut writer =
+            CompressedBlockFileCodec::<Vec<u8>>::with_compression(BlockFileCompression::Zstd);
+        let mut dst = BytesMut::new();
+        writer.encode(b"hello zstd".to_vec(), &mut dst).unwrap();
+
+        let mut reader = CompressedBlockFileCodec::<Vec<u8>>::default();
+        let decoded = reader.decode(&mut dst).unwrap().unwrap();
+        assert_eq!(decoded, b"hello zstd".to_vec());
+    }
+
+    #[test]
+    fn compressed_codec_round_trips_snappy() {
+        let mut writer =
+            CompressedBlockFileCodec::<Vec<u8>>::with_compression(BlockFileCompression::Snappy);
+        let mut dst = BytesMut::new();
+        writer.encode(b"hello snappy".to_vec(), &mut dst).unwrap();
+        writer.encode(b"second block".to_vec(), &mut dst).unwrap();
+
+        let mut reader = CompressedBlockFileCodec::<Vec<u8>>::default();
+        let first = reader.decode(&mut dst).unwrap().unwrap();
+        assert_eq!(first, b"hello snappy".to_vec());
+        let second = reader.decode(&mut dst).unwrap().unwrap();
+        assert_eq!(second, b"second block".to_vec());
+    }
+}